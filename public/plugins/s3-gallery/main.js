@@ -39,6 +39,14 @@ function s3gIsMobile() {
   return window.innerWidth <= 576
 }
 
+// 网格缩略图：走后端 thumb 协议（WebP 缓存），失败时回退原图
+function s3gThumbUrl(url) {
+  const u = String(url || '')
+  if (!/^(https?|s3):\/\//i.test(u)) return u
+  const base = /Windows|Android/i.test(navigator.userAgent || '') ? 'http://thumb.localhost/' : 'thumb://localhost/'
+  return base + '?size=256&url=' + encodeURIComponent(u)
+}
+
 function s3gIsTablet() {
   return window.innerWidth > 576 && window.innerWidth <= 768
 }
@@ -1049,7 +1057,11 @@ function renderList(records) {
     const url = rec.public_url || rec.publicUrl || ''
     if (url) {
       const img = document.createElement('img')
-      img.src = url
+      img.src = s3gThumbUrl(url)
+      img.onerror = () => {
+        img.onerror = null
+        img.src = url
+      }
       img.alt = rec.file_name || rec.key || ''
       img.style.maxWidth = '100%'
       img.style.maxHeight = '100%'
@@ -1880,7 +1880,7 @@ async function tmMakeKeyFromTemplate(template, fileName, contentType, bytesInput
   const second = pad2(now.getSeconds())
   const extName = tmGuessExtFromTypeOrName(contentType, fileName)
   const fileBase = tmBaseNameNoExt(fileName)
-  let key = template || '{year}/{month}{fileName}{md5}.{extName}'
+  let key = template || '{year}/{month}/{fileName}{md5}.{extName}'
   let md5 = ''
   try {
     const buf = u8.buffer.slice(u8.byteOffset, u8.byteOffset + u8.byteLength)
@@ -2085,7 +2085,7 @@ async function tmUploadViaImgLa(context, cfg, bytes, fileName, contentType) {
 
 async function tmUploadViaS3(context, cfg, bytes, fileName, contentType) {
   const u8 = bytes instanceof Uint8Array ? bytes : new Uint8Array(bytes || new ArrayBuffer(0))
-  const key = await tmMakeKeyFromTemplate(cfg.keyTemplate || '{year}/{month}{fileName}{md5}.{extName}', fileName, contentType, u8)
+  const key = await tmMakeKeyFromTemplate(cfg.keyTemplate || '{year}/{month}/{fileName}{md5}.{extName}', fileName, contentType, u8)
   const req = {
     accessKeyId: cfg.accessKeyId,
     secretAccessKey: cfg.secretAccessKey,
@@ -1275,11 +1275,55 @@ export async function setWebdavSyncConfigForLibrary(libIdInput: string, next: Pa
 // 统一通过 utils 获取当前库根目录（兼容 legacy）
 
 // 轻量 HTTP 客户端（优先使用 tauri plugin-http，回退到 fetch）
-async function getHttpClient(): Promise<{ fetch: any; ResponseType?: any; Body?: any } | null> {
+// 密码为凭据保险库引用（vault:<id>）时改由后端代发，明文密码只在后端解析
+async function getHttpClient(auth?: { username: string; password: string }): Promise<{ fetch: any; ResponseType?: any; Body?: any } | null> {
+  if (auth && isVaultRef(auth.password)) return getVaultHttpClient(auth)
   try { const mod: any = await import('@tauri-apps/plugin-http'); if (typeof mod?.fetch === 'function') return { fetch: mod.fetch, ResponseType: mod.ResponseType, Body: mod.Body } } catch {}
   try { return { fetch: (input: string, init: any) => fetch(input, init) } as any } catch { return null }
 }
 
+function isVaultRef(v: any): boolean {
+  return typeof v === 'string' && v.trim().startsWith('vault:')
+}
+
+function toBodyBytes(body: any): number[] | null {
+  if (body == null) return null
+  if (typeof body === 'string') return Array.from(new TextEncoder().encode(body))
+  if (body instanceof ArrayBuffer) return Array.from(new Uint8Array(body))
+  if (ArrayBuffer.isView(body)) return Array.from(new Uint8Array(body.buffer, body.byteOffset, body.byteLength))
+  return Array.from(new TextEncoder().encode(String(body)))
+}
+
+// 与 fetch 相同的调用方式；Authorization 由后端按保险库凭据补上，保险库锁定时直接报错
+function getVaultHttpClient(auth: { username: string; password: string }): { fetch: any } {
+  const fetchViaBackend = async (url: string, init: any) => {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const headers: Record<string, string> = {}
+    for (const [k, v] of Object.entries(init?.headers || {})) {
+      if (k.toLowerCase() !== 'authorization') headers[k] = String(v)
+    }
+    const resp: any = await invoke('flymd_webdav_sync_request', {
+      req: {
+        method: String(init?.method || 'GET'),
+        url,
+        username: auth.username,
+        password: auth.password,
+        headers,
+        body: toBodyBytes(init?.body),
+      }
+    })
+    const status = Number(resp?.status || 0)
+    const bytes = new Uint8Array(resp?.body || [])
+    return {
+      status,
+      ok: status >= 200 && status < 300,
+      text: async () => new TextDecoder().decode(bytes),
+      arrayBuffer: async () => bytes.buffer.slice(bytes.byteOffset, bytes.byteOffset + bytes.byteLength),
+    }
+  }
+  return { fetch: fetchViaBackend }
+}
+
 function joinUrl(...parts: string[]): string {
   const segs: string[] = []
   for (const p of parts) {
@@ -1425,7 +1469,7 @@ async function listRemoteDir(baseUrl: string, auth: { username: string; password
     return cached.result
   }
 
-  const http = await getHttpClient(); if (!http) throw new Error('no http client')
+  const http = await getHttpClient(auth); if (!http) throw new Error('no http client')
   const url = joinUrl(baseUrl, remotePath)
   const body = `<?xml version="1.0"?><d:propfind xmlns:d="DAV:"><d:prop><d:getlastmodified/><d:getetag/><d:resourcetype/></d:prop></d:propfind>`
   const headers: Record<string,string> = { Depth: '1', 'Content-Type': 'application/xml' }
@@ -1519,7 +1563,7 @@ async function listRemoteDir(baseUrl: string, auth: { username: string; password
 // 尝试使用 Depth: infinity 一次性获取整个目录树
 async function tryListRemoteInfinity(baseUrl: string, auth: { username: string; password: string }, rootPath: string): Promise<{ files: Map<string, FileEntry>; dirsProps: { [dir: string]: { mtime?: number; etag?: string } } } | null> {
   try {
-    const http = await getHttpClient(); if (!http) return null
+    const http = await getHttpClient(auth); if (!http) return null
     const url = joinUrl(baseUrl, rootPath)
     const body = `<?xml version="1.0"?><d:propfind xmlns:d="DAV:"><d:prop><d:getlastmodified/><d:getetag/><d:resourcetype/></d:prop></d:propfind>`
     const headers: Record<string,string> = { Depth: 'infinity', 'Content-Type': 'application/xml' }
@@ -1674,7 +1718,7 @@ async function listRemoteRecursively(
 }
 
 async function downloadFile(baseUrl: string, auth: { username: string; password: string }, remotePath: string): Promise<Uint8Array> {
-  const http = await getHttpClient(); if (!http) throw new Error('no http client')
+  const http = await getHttpClient(auth); if (!http) throw new Error('no http client')
   const url = joinUrl(baseUrl, remotePath)
   const authStr = btoa(`${auth.username}:${auth.password}`)
   const headers: Record<string,string> = { Authorization: `Basic ${authStr}` }
@@ -1692,7 +1736,7 @@ async function downloadFile(baseUrl: string, auth: { username: string; password:
 }
 
 async function uploadFile(baseUrl: string, auth: { username: string; password: string }, remotePath: string, data: Uint8Array): Promise<void> {
-  const http = await getHttpClient(); if (!http) throw new Error('no http client')
+  const http = await getHttpClient(auth); if (!http) throw new Error('no http client')
   const url = joinUrl(baseUrl, remotePath)
   const authStr = btoa(`${auth.username}:${auth.password}`)
   const headers: Record<string,string> = { Authorization: `Basic ${authStr}`, 'Content-Type': 'application/octet-stream' }
@@ -1706,7 +1750,7 @@ async function uploadFile(baseUrl: string, auth: { username: string; password: s
 }
 
 async function deleteRemoteFile(baseUrl: string, auth: { username: string; password: string }, remotePath: string): Promise<void> {
-  const http = await getHttpClient(); if (!http) throw new Error('no http client')
+  const http = await getHttpClient(auth); if (!http) throw new Error('no http client')
   const url = joinUrl(baseUrl, remotePath)
   const authStr = btoa(`${auth.username}:${auth.password}`)
   const headers: Record<string,string> = { Authorization: `Basic ${authStr}` }
@@ -1719,7 +1763,7 @@ async function deleteRemoteFile(baseUrl: string, auth: { username: string; passw
 }
 
 async function downloadFileAllow404(baseUrl: string, auth: { username: string; password: string }, remotePath: string): Promise<Uint8Array | null> {
-  const http = await getHttpClient(); if (!http) throw new Error('no http client')
+  const http = await getHttpClient(auth); if (!http) throw new Error('no http client')
   const url = joinUrl(baseUrl, remotePath)
   const authStr = btoa(`${auth.username}:${auth.password}`)
   const headers: Record<string,string> = { Authorization: `Basic ${authStr}` }
@@ -1746,7 +1790,7 @@ async function downloadFilePrefixAllow404(
   remotePath: string,
   maxBytes: number,
 ): Promise<Uint8Array | null> {
-  const http = await getHttpClient(); if (!http) throw new Error('no http client')
+  const http = await getHttpClient(auth); if (!http) throw new Error('no http client')
   const url = joinUrl(baseUrl, remotePath)
   const authStr = btoa(`${auth.username}:${auth.password}`)
   const headers: Record<string,string> = {
@@ -2078,7 +2122,7 @@ async function decryptBytesIfNeeded(data: Uint8Array, cfg: WebdavSyncConfig): Pr
 
 // WebDAV MOVE 操作：用于重命名远端文件
 async function moveRemoteFile(baseUrl: string, auth: { username: string; password: string }, fromPath: string, toPath: string): Promise<void> {
-  const http = await getHttpClient(); if (!http) throw new Error('no http client')
+  const http = await getHttpClient(auth); if (!http) throw new Error('no http client')
   const fromUrl = joinUrl(baseUrl, fromPath)
   const toUrl = joinUrl(baseUrl, toPath)
   const authStr = btoa(`${auth.username}:${auth.password}`)
@@ -3467,7 +3511,7 @@ export async function openWebdavSyncDialog(): Promise<void> {
 }
 // 远端目录保障：逐级 MKCOL 创建目录（已存在则忽略）
 async function mkcol(baseUrl: string, auth: { username: string; password: string }, remotePath: string): Promise<number> {
-  const http = await getHttpClient(); if (!http) return 0
+  const http = await getHttpClient(auth); if (!http) return 0
   const url = joinUrl(baseUrl, remotePath)
   const authStr = btoa(`${auth.username}:${auth.password}`)
   const headers: Record<string,string> = { Authorization: `Basic ${authStr}`}
@@ -276,8 +276,8 @@ const dict = {
     'upl.domain.ph': '例如 https://img.example.com',
     'upl.domain.hint': '填写后将使用该域名生成公开地址',
     'upl.template': '上传路径模板',
-    'upl.template.ph': '{year}/{month}{fileName}{md5}.{extName}',
-    'upl.template.hint': '可用变量：{year}{month}{day}{fileName}{md5}{extName}',
+    'upl.template.ph': '{year}/{month}/{fileName}{md5}.{extName}',
+    'upl.template.hint': '可用变量：{year}{month}{day}{hour}{minute}{second}{timestamp}{fileName}{extName}{md5}{sha256}{uuid}{docname}；哈希可截断，如 {md5:8}、{sha256:8}；{docname} 仅在批量上传等关联文档时有值',
     'upl.section.advanced': '高级选项',
     'upl.pathstyle': 'Path-Style（R2 建议）',
     'upl.acl': 'public-read',
@@ -778,8 +778,8 @@ const dict = {
     'upl.domain.ph': 'e.g. https://img.example.com',
     'upl.domain.hint': 'If set, the public URL will use this domain.',
     'upl.template': 'Upload Path Template',
-    'upl.template.ph': '{year}/{month}{fileName}{md5}.{extName}',
-    'upl.template.hint': 'Available vars: {year}{month}{day}{fileName}{md5}{extName}',
+    'upl.template.ph': '{year}/{month}/{fileName}{md5}.{extName}',
+    'upl.template.hint': 'Available vars: {year}{month}{day}{hour}{minute}{second}{timestamp}{fileName}{extName}{md5}{sha256}{uuid}{docname}; hashes can be truncated, e.g. {md5:8} {sha256:8}; {docname} is only set when the upload belongs to a document (e.g. batch upload)',
     'upl.section.advanced': 'Advanced',
     'upl.pathstyle': 'Path-Style (recommended for R2)',
     'upl.acl': 'public-read',
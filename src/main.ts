@@ -3567,6 +3567,15 @@ async function renderPreview(opts?: RenderPreviewOptions) {
       try {
         const el = img as HTMLImageElement
         const src = el.getAttribute('src') || ''
+        // 私有桶图片：s3://bucket/key 交给后端 s3 协议（按需生成短期签名 URL 并代理读取）
+        if (/^s3:\/\//i.test(src)) {
+          // 保留原始引用，导出时据此换成签名 URL
+          el.setAttribute('data-s3-src', src)
+          void ensurePrivateS3BucketRegistered(s3RefBucket(src)).then(() => {
+            if (typeof convertFileSrc === 'function') el.src = convertFileSrc(src.slice(5), 's3')
+          })
+          return
+        }
         const abs0 = resolveLocalImageAbsPathFromSrc(src, currentFilePath)
         if (!abs0) return
         let abs: string = abs0
@@ -4686,6 +4695,7 @@ async function exportCurrentDocToPdf(target: string): Promise<void> {
 
     const el = preview.querySelector('.preview-body') as HTMLElement | null
     if (!el) throw new Error('未找到预览内容容器')
+    await resolvePrivateS3ImagesForExport(el)
 
     const { exportPdf } = await import('./exporters/pdf')
     const fmt = (v: any) => {
@@ -4789,6 +4799,7 @@ async function saveAs() {
           overlay.appendLog('预览渲染完成')
             const el = preview.querySelector('.preview-body') as HTMLElement | null;
             if (!el) throw new Error('未找到预览内容容器');
+            await resolvePrivateS3ImagesForExport(el);
             const { exportPdf } = await import('./exporters/pdf');
             const fmt = (v: any) => {
               try { return typeof v === 'string' ? v : JSON.stringify(v) } catch { return String(v) }
@@ -4829,6 +4840,7 @@ async function saveAs() {
           await renderPreview({ forPrint: true });
           const el = preview.querySelector('.preview-body') as HTMLElement | null;
           if (!el) throw new Error('未找到预览内容容器');
+          await resolvePrivateS3ImagesForExport(el);
           const html = el.outerHTML;
           if (ext === 'docx') {
             const { exportDocx } = await import('./exporters/docx');
@@ -5637,6 +5649,98 @@ async function getUploaderRawConfig(): Promise<AnyUploaderConfig | null> {
   } catch { return null }
 }
 
+// 私有桶渲染：把当前 S3 图床以及曾经配置过的 S3 桶（privateS3Buckets，切换图床时由设置对话框记录，
+// 密钥为 vault:<id> 引用）的凭据注册到后端（仅内存），供 s3:// 协议签名使用；笔记里可能引用不同桶的图片
+let _privateS3Registered: Promise<Set<string>> | null = null
+const _privateS3Retried = new Set<string>()
+async function registerPrivateS3Buckets(): Promise<Set<string>> {
+  const done = new Set<string>()
+  const list: any[] = []
+  try {
+    const cfg: any = await getUploaderRawConfig()
+    if (cfg && cfg.provider === 's3') list.push(cfg)
+  } catch {}
+  try {
+    const saved = store ? await store.get('privateS3Buckets') : null
+    if (Array.isArray(saved)) {
+      // 只使用保险库引用；旧版本记录的明文密钥从存储中清除
+      const kept = saved.filter((b: any) => typeof b?.secretAccessKey === 'string' && b.secretAccessKey.trim().startsWith('vault:'))
+      list.push(...kept)
+      if (store && kept.length !== saved.length) {
+        await store.set('privateS3Buckets', kept)
+        await store.save()
+      }
+    }
+  } catch {}
+  for (const cfg of list) {
+    const bucket = String(cfg?.bucket || '').trim()
+    if (!bucket || done.has(bucket)) continue
+    try {
+      await invoke('flymd_s3_register_private_bucket', {
+        req: {
+          accessKeyId: cfg.accessKeyId,
+          secretAccessKey: cfg.secretAccessKey,
+          bucket,
+          region: cfg.region,
+          endpoint: cfg.endpoint,
+          forcePathStyle: cfg.forcePathStyle !== false,
+        },
+      })
+      done.add(bucket)
+    } catch (e) {
+      console.warn('[Uploader] 注册私有桶失败', bucket, e)
+    }
+  }
+  return done
+}
+async function ensurePrivateS3BucketRegistered(bucket: string): Promise<void> {
+  if (!_privateS3Registered) _privateS3Registered = registerPrivateS3Buckets()
+  const done = await _privateS3Registered
+  // 未注册的桶：图床设置可能刚修改过，每个桶重新读取一次配置
+  if (done.has(bucket) || _privateS3Retried.has(bucket)) return
+  _privateS3Retried.add(bucket)
+  _privateS3Registered = registerPrivateS3Buckets()
+  await _privateS3Registered
+}
+
+function s3RefBucket(src: string): string {
+  return String(src || '').replace(/^s3:\/\//i, '').split('/')[0] || ''
+}
+
+// 导出用：预览中的 s3:// 图片只能在本应用内通过 s3 协议显示，导出前换成短期签名 URL，
+// 避免导出的 HTML / DOCX / PDF 中残留 s3:// 或本地协议链接
+async function resolvePrivateS3ImagesForExport(root: HTMLElement): Promise<void> {
+  const imgs = Array.from(root.querySelectorAll('img')) as HTMLImageElement[]
+  for (const img of imgs) {
+    const src = img.getAttribute('data-s3-src') || img.getAttribute('src') || ''
+    if (!/^s3:\/\//i.test(src)) continue
+    try {
+      await ensurePrivateS3BucketRegistered(s3RefBucket(src))
+      const url = await invoke<string>('flymd_s3_resolve_url', { src })
+      img.setAttribute('src', url)
+      img.removeAttribute('data-s3-src')
+    } catch (e) {
+      console.warn('[Export] 私有桶图片签名失败', src, e)
+    }
+  }
+}
+
+// Markdown 文本中的 s3:// 链接（图片与普通链接）换成短期签名 URL；供导出类插件使用
+async function resolvePrivateS3LinksInMarkdown(md: string): Promise<string> {
+  const refs = Array.from(new Set(String(md || '').match(/s3:\/\/[^\s)"'<>]+/gi) || []))
+  let out = String(md || '')
+  for (const src of refs) {
+    try {
+      await ensurePrivateS3BucketRegistered(s3RefBucket(src))
+      const url = await invoke<string>('flymd_s3_resolve_url', { src })
+      out = out.split(src).join(url)
+    } catch (e) {
+      console.warn('[Export] 私有桶链接签名失败', src, e)
+    }
+  }
+  return out
+}
+
 // 将获取上传配置的方法暴露到全局，供所见 V2 的上传插件使用
 try {
   if (typeof window !== 'undefined') {
@@ -5653,6 +5757,7 @@ try {
     ;(window as any).flymdAlwaysSaveLocalImages = () => getAlwaysSaveLocalImages()
     ;(window as any).flymdPreferRelativeLocalImages = () => getPreferRelativeLocalImages()
     ;(window as any).flymdSaveImageToLocalAndGetPath = (file: File, name: string, force?: boolean) => saveImageToLocalAndGetPath(file, name, force)
+    ;(window as any).flymdResolveS3Links = (md: string) => resolvePrivateS3LinksInMarkdown(md)
   }
 } catch {}
 
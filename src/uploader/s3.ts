@@ -1,8 +1,9 @@
 import { invoke } from '@tauri-apps/api/core'
 import type { S3UploaderConfig } from './types'
+import { DEFAULT_KEY_TEMPLATE } from './storeConfig'
 // 直连 S3/R2（SigV4）最小实现：
 // - 支持 path-style 与自定义域名
-// - 默认模板 {year}/{month}{fileName}{md5}.{extName}
+// - key 由后端 render_key_template 按模板渲染（默认 {year}/{month}/{fileName}{md5}.{extName}），与批量上传/离线队列一致
 // - 仅依赖 Web Crypto（SHA-256/HMAC-SHA256）+ 轻量 MD5 实现
 
 export type UploaderConfig = S3UploaderConfig
@@ -58,109 +59,6 @@ async function deriveSigningKey(secretAccessKey: string, date: string, region: s
   return kSigning
 }
 
-// 极简 MD5（基于常见实现改写为 TS，无外部依赖）
-function md5Hex(buf: ArrayBuffer): string {
-  const x = new Uint8Array(buf)
-  const len = x.length
-  const words = new Uint32Array(((len + 8 >>> 6) + 1) << 4)
-  for (let i = 0; i < len; i++) words[i >> 2] |= x[i] << ((i % 4) << 3)
-  const bitLen = len * 8
-  words[bitLen >> 5] |= 0x80 << (bitLen % 32)
-  words[((bitLen + 64 >>> 9) << 4) + 14] = bitLen
-  let a = 1732584193, b = -271733879, c = -1732584194, d = 271733878
-  const ff = (a: number, b: number, c: number, d: number, x: number, s: number, t: number) => ((a + ((b & c) | (~b & d)) + x + t) << s | (a + ((b & c) | (~b & d)) + x + t) >>> (32 - s)) + b | 0
-  const gg = (a: number, b: number, c: number, d: number, x: number, s: number, t: number) => ((a + ((b & d) | (c & ~d)) + x + t) << s | (a + ((b & d) | (c & ~d)) + x + t) >>> (32 - s)) + b | 0
-  const hh = (a: number, b: number, c: number, d: number, x: number, s: number, t: number) => ((a + (b ^ c ^ d) + x + t) << s | (a + (b ^ c ^ d) + x + t) >>> (32 - s)) + b | 0
-  const ii = (a: number, b: number, c: number, d: number, x: number, s: number, t: number) => ((a + (c ^ (b | ~d)) + x + t) << s | (a + (c ^ (b | ~d)) + x + t) >>> (32 - s)) + b | 0
-  for (let i = 0; i < words.length; i += 16) {
-    const oa = a, ob = b, oc = c, od = d
-    a = ff(a, b, c, d, words[i + 0], 7, -680876936)
-    d = ff(d, a, b, c, words[i + 1], 12, -389564586)
-    c = ff(c, d, a, b, words[i + 2], 17, 606105819)
-    b = ff(b, c, d, a, words[i + 3], 22, -1044525330)
-    a = ff(a, b, c, d, words[i + 4], 7, -176418897)
-    d = ff(d, a, b, c, words[i + 5], 12, 1200080426)
-    c = ff(c, d, a, b, words[i + 6], 17, -1473231341)
-    b = ff(b, c, d, a, words[i + 7], 22, -45705983)
-    a = ff(a, b, c, d, words[i + 8], 7, 1770035416)
-    d = ff(d, a, b, c, words[i + 9], 12, -1958414417)
-    c = ff(c, d, a, b, words[i + 10], 17, -42063)
-    b = ff(b, c, d, a, words[i + 11], 22, -1990404162)
-    a = ff(a, b, c, d, words[i + 12], 7, 1804603682)
-    d = ff(d, a, b, c, words[i + 13], 12, -40341101)
-    c = ff(c, d, a, b, words[i + 14], 17, -1502002290)
-    b = ff(b, c, d, a, words[i + 15], 22, 1236535329)
-    a = gg(a, b, c, d, words[i + 1], 5, -165796510)
-    d = gg(d, a, b, c, words[i + 6], 9, -1069501632)
-    c = gg(c, d, a, b, words[i + 11], 14, 643717713)
-    b = gg(b, c, d, a, words[i + 0], 20, -373897302)
-    a = gg(a, b, c, d, words[i + 5], 5, -701558691)
-    d = gg(d, a, b, c, words[i + 10], 9, 38016083)
-    c = gg(c, d, a, b, words[i + 15], 14, -660478335)
-    b = gg(b, c, d, a, words[i + 4], 20, -405537848)
-    a = gg(a, b, c, d, words[i + 9], 5, 568446438)
-    d = gg(d, a, b, c, words[i + 14], 9, -1019803690)
-    c = gg(c, d, a, b, words[i + 3], 14, -187363961)
-    b = gg(b, c, d, a, words[i + 8], 20, 1163531501)
-    a = gg(a, b, c, d, words[i + 13], 5, -1444681467)
-    d = gg(d, a, b, c, words[i + 2], 9, -51403784)
-    c = gg(c, d, a, b, words[i + 7], 14, 1735328473)
-    b = gg(b, c, d, a, words[i + 12], 20, -1926607734)
-    a = hh(a, b, c, d, words[i + 5], 4, -378558)
-    d = hh(d, a, b, c, words[i + 8], 11, -2022574463)
-    c = hh(c, d, a, b, words[i + 11], 16, 1839030562)
-    b = hh(b, c, d, a, words[i + 14], 23, -35309556)
-    a = hh(a, b, c, d, words[i + 1], 4, -1530992060)
-    d = hh(d, a, b, c, words[i + 4], 11, 1272893353)
-    c = hh(c, d, a, b, words[i + 7], 16, -155497632)
-    b = hh(b, c, d, a, words[i + 10], 23, -1094730640)
-    a = hh(a, b, c, d, words[i + 13], 4, 681279174)
-    d = hh(d, a, b, c, words[i + 0], 11, -358537222)
-    c = hh(c, d, a, b, words[i + 3], 16, -722521979)
-    b = hh(b, c, d, a, words[i + 6], 23, 76029189)
-    a = ii(a, b, c, d, words[i + 0], 6, -198630844)
-    d = ii(d, a, b, c, words[i + 7], 10, 1126891415)
-    c = ii(c, d, a, b, words[i + 14], 15, -1416354905)
-    b = ii(b, c, d, a, words[i + 5], 21, -57434055)
-    a = ii(a, b, c, d, words[i + 12], 6, 1700485571)
-    d = ii(d, a, b, c, words[i + 3], 10, -1894986606)
-    c = ii(c, d, a, b, words[i + 10], 15, -1051523)
-    b = ii(b, c, d, a, words[i + 1], 21, -2054922799)
-    a = ii(a, b, c, d, words[i + 8], 6, 1873313359)
-    d = ii(d, a, b, c, words[i + 15], 10, -30611744)
-    c = ii(c, d, a, b, words[i + 6], 15, -1560198380)
-    b = ii(b, c, d, a, words[i + 13], 21, 1309151649)
-    a = (a + oa) | 0
-    b = (b + ob) | 0
-    c = (c + oc) | 0
-    d = (d + od) | 0
-  }
-  const r = new DataView(new ArrayBuffer(16))
-  r.setUint32(0, a, true)
-  r.setUint32(4, b, true)
-  r.setUint32(8, c, true)
-  r.setUint32(12, d, true)
-  return toHex(r.buffer)
-}
-
-function guessExtFromTypeOrName(contentType: string, name: string): string {
-  const m = name.toLowerCase().match(/\.([a-z0-9]+)$/)
-  if (m) return m[1]
-  if (/jpeg/.test(contentType)) return 'jpg'
-  if (/png/.test(contentType)) return 'png'
-  if (/gif/.test(contentType)) return 'gif'
-  if (/webp/.test(contentType)) return 'webp'
-  if (/bmp/.test(contentType)) return 'bmp'
-  if (/avif/.test(contentType)) return 'avif'
-  if (/svg/.test(contentType)) return 'svg'
-  return 'png'
-}
-
-function baseNameNoExt(name: string): string {
-  const n = name.split(/[\\/]+/).pop() || name
-  return n.replace(/\.[^.]+$/, '')
-}
-
 function pad2(n: number): string { return n < 10 ? '0' + n : '' + n }
 
 function formatAmzDate(): { amzDate: string; dateStamp: string } {
@@ -176,32 +74,6 @@ function formatAmzDate(): { amzDate: string; dateStamp: string } {
   return { amzDate, dateStamp }
 }
 
-async function makeKeyFromTemplate(template: string, fileName: string, contentType: string, bytes: ArrayBuffer): Promise<string> {
-  const now = new Date()
-  const year = String(now.getFullYear())
-  const month = pad2(now.getMonth() + 1)
-  const day = pad2(now.getDate())
-  const hour = pad2(now.getHours())
-  const minute = pad2(now.getMinutes())
-  const second = pad2(now.getSeconds())
-  const extName = guessExtFromTypeOrName(contentType, fileName)
-  const fileBase = baseNameNoExt(fileName)
-  let key = template || '{year}/{month}{fileName}{md5}.{extName}'
-  let md5 = ''
-  try { md5 = md5Hex(bytes) } catch { md5 = '' }
-  key = key
-    .replace(/\{year\}/g, year)
-    .replace(/\{month\}/g, month)
-    .replace(/\{day\}/g, day)
-    .replace(/\{hour\}/g, hour)
-    .replace(/\{minute\}/g, minute)
-    .replace(/\{second\}/g, second)
-    .replace(/\{fileName\}/g, fileBase)
-    .replace(/\{extName\}/g, extName)
-    .replace(/\{md5\}/g, md5)
-  return key.replace(/^\/+/, '')
-}
-
 function ensureEndpointUrl(endpoint?: string): URL {
   const ep = (endpoint || '').trim()
   if (!ep) return new URL('https://s3.amazonaws.com')
@@ -267,68 +139,75 @@ export async function uploadImageToS3R2(input: Blob | ArrayBuffer | Uint8Array,
   else if (input instanceof Uint8Array) bytes = input.buffer
   else bytes = input
 
-  const key = await makeKeyFromTemplate(cfg.keyTemplate || '{year}/{month}{fileName}{md5}.{extName}', fileName, contentType, bytes)
-  // 方案A：优先使用后端 SDK 直传（与 PicList 一致）
-  if (isTauriRuntime()) {
+  // 浏览器环境没有后端，无法按模板渲染 key
+  if (!isTauriRuntime()) throw new Error('S3 上传需要在桌面端使用')
+  const keyTemplate = cfg.keyTemplate || DEFAULT_KEY_TEMPLATE
+  const byteArray = Array.from(new Uint8Array(bytes))
+  // 方案A：优先使用后端 SDK 直传（与 PicList 一致）；key 由后端渲染并处理同名冲突，历史记录使用返回的 key
+  try {
+    const resp = await invoke<{ key: string; public_url: string }>('upload_to_s3', {
+      req: {
+        accessKeyId: cfg.accessKeyId,
+        secretAccessKey: cfg.secretAccessKey,
+        bucket: cfg.bucket,
+        region,
+        endpoint: cfg.endpoint,
+        forcePathStyle: forcePathStyle,
+        aclPublicRead: aclPublicRead,
+        customDomain: cfg.customDomain,
+        keyTemplate,
+        onConflict: cfg.onConflict,
+        fileName,
+        contentType,
+        bytes: byteArray
+      }
+    })
+    const publicUrl = resp.public_url
+    await recordUploadHistoryIfPossible(resp.key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
+    return { key: resp.key, publicUrl }
+  } catch (e) {
+    // 兜底路径无法检查同名对象：设置了冲突策略时直接报错，避免覆盖已有对象
+    if (cfg.onConflict && cfg.onConflict !== 'overwrite') throw e
+    console.warn('upload_to_s3 (sdk) failed, fallback to presign', e)
+  }
+  // SDK 未能返回 key：兜底路径同样由后端按模板渲染
+  const key = await invoke<string>('flymd_render_key_template', { req: { keyTemplate, fileName, contentType, bytes: byteArray } })
+  // 方案B 作为兜底：预签名 + PUT（插件/浏览器）
+  try {
+    const pres = await invoke<{ put_url: string; public_url: string; headers?: Record<string, string> }>('presign_put', {
+      req: {
+        accessKeyId: cfg.accessKeyId,
+        secretAccessKey: cfg.secretAccessKey,
+        bucket: cfg.bucket,
+        region,
+        endpoint: cfg.endpoint,
+        forcePathStyle: forcePathStyle,
+        customDomain: cfg.customDomain,
+        key,
+        expires: 600
+      }
+    })
+    // 插件优先
     try {
-        const resp = await invoke<{ key: string; public_url: string }>('upload_to_s3', {
-          req: {
-            accessKeyId: cfg.accessKeyId,
-            secretAccessKey: cfg.secretAccessKey,
-            bucket: cfg.bucket,
-            region,
-          endpoint: cfg.endpoint,
-          forcePathStyle: forcePathStyle,
-          aclPublicRead: aclPublicRead,
-            customDomain: cfg.customDomain,
-            key,
-            contentType,
-            bytes: Array.from(new Uint8Array(bytes))
-          }
-        })
-        const publicUrl = resp.public_url
-        await recordUploadHistoryIfPossible(resp.key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
-        return { key: resp.key, publicUrl }
-      } catch (e) {
-      console.warn('upload_to_s3 (sdk) failed, fallback to presign', e)
-      // 方案B 作为兜底：预签名 + PUT（插件/浏览器）
-      try {
-        const pres = await invoke<{ put_url: string; public_url: string }>('presign_put', {
-          req: {
-            accessKeyId: cfg.accessKeyId,
-            secretAccessKey: cfg.secretAccessKey,
-            bucket: cfg.bucket,
-            region,
-            endpoint: cfg.endpoint,
-            forcePathStyle: forcePathStyle,
-            customDomain: cfg.customDomain,
-            key,
-            expires: 600
-          }
-        })
-        // 插件优先
-          try {
-            const client = await tryPluginHttp()
-            if (client && client.fetch && client.Body) {
-              const body = new Uint8Array(bytes)
-              const r1 = await client.fetch(pres.put_url, { method: 'PUT', body: client.Body.bytes(body) })
-              if (r1 && (r1.ok === true || (typeof r1.status === 'number' && r1.status >= 200 && r1.status < 300))) {
-                const publicUrl = pres.public_url
-                await recordUploadHistoryIfPossible(key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
-                return { key, publicUrl }
-              }
-            }
-          } catch {}
-          const r2 = await fetch(pres.put_url, { method: 'PUT', body: bytes })
-          if (r2.ok) {
-            const publicUrl = pres.public_url
-            await recordUploadHistoryIfPossible(key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
-            return { key, publicUrl }
-          }
-      } catch {}
-      // 仍失败则走本地兜底
+      const client = await tryPluginHttp()
+      if (client && client.fetch && client.Body) {
+        const body = new Uint8Array(bytes)
+        const r1 = await client.fetch(pres.put_url, { method: 'PUT', headers: pres.headers || {}, body: client.Body.bytes(body) })
+        if (r1 && (r1.ok === true || (typeof r1.status === 'number' && r1.status >= 200 && r1.status < 300))) {
+          const publicUrl = pres.public_url
+          await recordUploadHistoryIfPossible(key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
+          return { key, publicUrl }
+        }
+      }
+    } catch {}
+    const r2 = await fetch(pres.put_url, { method: 'PUT', headers: pres.headers || {}, body: bytes })
+    if (r2.ok) {
+      const publicUrl = pres.public_url
+      await recordUploadHistoryIfPossible(key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
+      return { key, publicUrl }
     }
-  }
+  } catch {}
+  // 仍失败则走本地签名直传
   const { url, hostForSig, canonicalUri } = buildUploadUrl(endpointUrl, cfg.bucket, key, forcePathStyle)
 
   const { amzDate, dateStamp } = formatAmzDate()
@@ -359,40 +238,20 @@ export async function uploadImageToS3R2(input: Blob | ArrayBuffer | Uint8Array,
   const signature = toHex(await hmacSha256Raw(signingKey, stringToSign))
   const authorization = `AWS4-HMAC-SHA256 Credential=${cfg.accessKeyId}/${credentialScope}, SignedHeaders=${signedHeaders}, Signature=${signature}`
 
-  // 优先尝试 Tauri plugin-http（可绕过 CORS）；如不可用再回退到浏览器 fetch。
-  // Tauri 环境下，若插件不可用，直接抛错让上层回退为本地，不再走浏览器 fetch（避免 CORS）
-  if (isTauriRuntime()) {
-    const client = await tryPluginHttp()
-    if (client && client.fetch && client.Body) {
-      const h: Record<string, string> = {}
-      for (const k of Object.keys(headers)) h[k] = headers[k]
-      h['authorization'] = authorization
-      const body = new Uint8Array(bytes)
-      const resp = await client.fetch(url.toString(), { method: 'PUT', headers: h, body: client.Body.bytes(body) })
-      const ok = resp && (resp.ok === true || (typeof resp.status === 'number' && resp.status >= 200 && resp.status < 300))
-      if (!ok) {
-        const status = resp?.status ?? 0
-        const statusText = resp?.statusText ?? ''
-        throw new Error(`upload failed via plugin-http: ${status} ${statusText}`)
-      }
-      const publicUrl = buildPublicUrl(cfg.customDomain, endpointUrl, cfg.bucket, key, forcePathStyle)
-      await recordUploadHistoryIfPossible(key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
-      return { key, publicUrl }
-    } else {
-      throw new Error('tauri plugin-http not available')
-    }
+  // 只走 Tauri plugin-http（可绕过 CORS）；插件不可用时直接抛错让上层回退为本地
+  const client = await tryPluginHttp()
+  if (!client || !client.fetch || !client.Body) throw new Error('tauri plugin-http not available')
+  const h: Record<string, string> = {}
+  for (const k of Object.keys(headers)) h[k] = headers[k]
+  h['authorization'] = authorization
+  const body = new Uint8Array(bytes)
+  const resp = await client.fetch(url.toString(), { method: 'PUT', headers: h, body: client.Body.bytes(body) })
+  const ok = resp && (resp.ok === true || (typeof resp.status === 'number' && resp.status >= 200 && resp.status < 300))
+  if (!ok) {
+    const status = resp?.status ?? 0
+    const statusText = resp?.statusText ?? ''
+    throw new Error(`upload failed via plugin-http: ${status} ${statusText}`)
   }
-
-  const putHeaders: HeadersInit = new Headers()
-  for (const k of Object.keys(headers)) putHeaders.set(k, headers[k])
-  putHeaders.set('authorization', authorization)
-
-  const res = await fetch(url.toString(), { method: 'PUT', headers: putHeaders, body: bytes })
-  if (!res.ok) {
-    const text = await (async () => { try { return await res.text() } catch { return '' } })()
-    throw new Error(`upload failed: ${res.status} ${res.statusText} ${text}`)
-  }
-
   const publicUrl = buildPublicUrl(cfg.customDomain, endpointUrl, cfg.bucket, key, forcePathStyle)
   await recordUploadHistoryIfPossible(key, publicUrl, cfg, fileName, contentType, bytes.byteLength || 0)
   return { key, publicUrl }
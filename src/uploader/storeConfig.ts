@@ -5,6 +5,11 @@ import type { AnyUploaderConfig, ImgLaUploaderConfig, S3UploaderConfig, Uploader
 
 const IMGLA_BASE_URL = 'https://www.imgla.net'
 
+// 默认上传路径模板
+export const DEFAULT_KEY_TEMPLATE = '{year}/{month}/{fileName}{md5}.{extName}'
+// 旧版默认模板（月份与文件名之间没有 /）：已保存但未填写模板的配置继续沿用，避免新上传的对象换了目录结构
+export const LEGACY_KEY_TEMPLATE = '{year}/{month}{fileName}{md5}.{extName}'
+
 function normStr(v: unknown): string {
   return String(v ?? '').trim()
 }
@@ -86,7 +91,8 @@ export function parseUploaderConfigForManagement(
     region: typeof raw.region === 'string' ? raw.region : undefined,
     endpoint: typeof raw.endpoint === 'string' ? raw.endpoint : undefined,
     customDomain: typeof raw.customDomain === 'string' ? raw.customDomain : undefined,
-    keyTemplate: typeof raw.keyTemplate === 'string' ? raw.keyTemplate : '{year}/{month}{fileName}{md5}.{extName}',
+    keyTemplate: normStr(raw.keyTemplate) || LEGACY_KEY_TEMPLATE,
+    onConflict: ['overwrite', 'rename', 'skip', 'error'].includes(normStr(raw.onConflict)) ? (normStr(raw.onConflict) as S3UploaderConfig['onConflict']) : undefined,
     aclPublicRead: raw.aclPublicRead !== false,
     forcePathStyle: raw.forcePathStyle !== false,
     convertToWebp,
@@ -21,6 +21,8 @@ export type S3UploaderConfig = UploaderCommon & {
   endpoint?: string
   customDomain?: string
   keyTemplate?: string
+  // 同名对象的处理：overwrite（默认）/ rename（追加 -1、-2…）/ skip（沿用已有对象）/ error
+  onConflict?: 'overwrite' | 'rename' | 'skip' | 'error'
   aclPublicRead?: boolean
   forcePathStyle?: boolean
 }
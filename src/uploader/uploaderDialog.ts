@@ -4,6 +4,7 @@
 import type { Store } from '@tauri-apps/plugin-store'
 import { invoke } from '@tauri-apps/api/core'
 import { openUrl } from '@tauri-apps/plugin-opener'
+import { DEFAULT_KEY_TEMPLATE, LEGACY_KEY_TEMPLATE } from './storeConfig'
 
 const IMGLA_BASE_URL = 'https://www.imgla.net'
 
@@ -49,6 +50,53 @@ export async function testUploaderConnectivity(endpoint: string): Promise<{ ok:
   }
 }
 
+// 记录配置过的 S3 桶：切换到其它桶 / 图床后，旧笔记里的 s3:// 私有桶图片仍能签名显示。
+// 只保存桶、endpoint、region 与 AccessKeyId（本就出现在签名 URL 中）；SecretAccessKey 存入凭据保险库，
+// 这里只留 vault:<id> 引用，保险库未解锁时不记录。凭据被清空的桶与旧版本留下的明文条目一并移除
+function isVaultRef(v: any): boolean {
+  return typeof v === 'string' && v.trim().startsWith('vault:')
+}
+
+function privateS3VaultId(bucket: string): string {
+  return `private-s3-${bucket}`
+}
+
+async function rememberPrivateS3Bucket(store: Store, cfg: any): Promise<void> {
+  const saved = await store.get('privateS3Buckets')
+  const prev: any[] = Array.isArray(saved) ? saved.filter((b: any) => b && b.bucket) : []
+  let list = prev.filter((b: any) => isVaultRef(b.secretAccessKey))
+  if (cfg.provider === 's3' && cfg.bucket) {
+    list = list.filter((b: any) => b.bucket !== cfg.bucket)
+    if (cfg.accessKeyId && cfg.secretAccessKey) {
+      let ref: string | null = isVaultRef(cfg.secretAccessKey) ? String(cfg.secretAccessKey).trim() : null
+      if (!ref) {
+        try {
+          ref = await invoke<string>('flymd_vault_set_secret', {
+            req: { id: privateS3VaultId(cfg.bucket), value: cfg.secretAccessKey, label: `S3 私有桶 ${cfg.bucket}` },
+          })
+        } catch {}
+      }
+      if (ref) {
+        list.push({
+          bucket: cfg.bucket,
+          accessKeyId: cfg.accessKeyId,
+          secretAccessKey: ref,
+          region: cfg.region,
+          endpoint: cfg.endpoint,
+          forcePathStyle: cfg.forcePathStyle,
+        })
+      }
+    }
+  }
+  // 不再记录的桶：删除由这里存入保险库的密钥（保险库锁定时留待下次保存）
+  for (const b of prev) {
+    if (list.some((x: any) => x.bucket === b.bucket)) continue
+    if (String(b.secretAccessKey || '').trim() !== `vault:${privateS3VaultId(b.bucket)}`) continue
+    try { await invoke('flymd_vault_delete_secret', { id: privateS3VaultId(b.bucket) }) } catch {}
+  }
+  await store.set('privateS3Buckets', list)
+}
+
 // 打开图床设置对话框：读取配置并绑定交互
 export async function openUploaderDialog(deps: UploaderDialogDeps): Promise<void> {
   const overlay = document.getElementById('uploader-overlay') as HTMLDivElement | null
@@ -216,7 +264,8 @@ export async function openUploaderDialog(deps: UploaderDialogDeps): Promise<void
       inputEndpoint.value = up?.endpoint || ''
       inputRegion.value = up?.region || ''
       inputDomain.value = up?.customDomain || ''
-      inputTpl.value = up?.keyTemplate || '{year}/{month}{fileName}{md5}.{extName}'
+      // 已有的 S3 配置未填写模板时显示旧版默认值，保存后不改变既有的目录结构
+      inputTpl.value = up?.keyTemplate || (up?.bucket ? LEGACY_KEY_TEMPLATE : DEFAULT_KEY_TEMPLATE)
       inputPathStyle.checked = up?.forcePathStyle !== false
       inputAcl.checked = up?.aclPublicRead !== false
       inputImglaBaseUrl.value = (up?.imglaBaseUrl || up?.baseUrl || IMGLA_BASE_URL)
@@ -276,7 +325,7 @@ export async function openUploaderDialog(deps: UploaderDialogDeps): Promise<void
           endpoint: inputEndpoint.value.trim() || undefined,
           region: inputRegion.value.trim() || undefined,
           customDomain: inputDomain.value.trim() || undefined,
-          keyTemplate: inputTpl.value.trim() || '{year}/{month}{fileName}{md5}.{extName}',
+          keyTemplate: inputTpl.value.trim() || DEFAULT_KEY_TEMPLATE,
           forcePathStyle: !!inputPathStyle.checked,
           aclPublicRead: !!inputAcl.checked,
           imglaBaseUrl: inputImglaBaseUrl.value.trim() || '',
@@ -309,6 +358,7 @@ export async function openUploaderDialog(deps: UploaderDialogDeps): Promise<void
         }
         if (store) {
           await store.set('uploader', cfg)
+          await rememberPrivateS3Bucket(store, cfg)
           await store.save()
           deps.setUploaderEnabledSnapshot(!!cfg.enabled)
         }
@@ -368,7 +418,7 @@ export async function openUploaderDialog(deps: UploaderDialogDeps): Promise<void
         endpoint: inputEndpoint.value.trim() || undefined,
         region: inputRegion.value.trim() || undefined,
         customDomain: inputDomain.value.trim() || undefined,
-        keyTemplate: inputTpl.value.trim() || '{year}/{month}{fileName}{md5}.{extName}',
+        keyTemplate: inputTpl.value.trim() || DEFAULT_KEY_TEMPLATE,
         forcePathStyle: !!inputPathStyle.checked,
         aclPublicRead: !!inputAcl.checked,
         imglaToken: inputImglaToken.value.trim() || '',
@@ -397,6 +447,7 @@ export async function openUploaderDialog(deps: UploaderDialogDeps): Promise<void
       }
       if (store) {
         await store.set('uploader', cfg)
+        await rememberPrivateS3Bucket(store, cfg)
         await store.save()
         deps.setUploaderEnabledSnapshot(!!cfg.enabled)
       }